@@ -7,7 +7,7 @@
 use crate::{
     point, px, size, AnyElement, AvailableSpace, Bounds, ContentMask, Element, ElementId,
     GlobalElementId, Hitbox, InteractiveElement, Interactivity, IntoElement, LayoutId, Pixels,
-    Render, ScrollHandle, Size, StyleRefinement, Styled, View, ViewContext, WindowContext,
+    Point, Render, ScrollHandle, Size, StyleRefinement, Styled, View, ViewContext, WindowContext,
 };
 use smallvec::SmallVec;
 use std::{cell::RefCell, cmp, ops::Range, rc::Rc};
@@ -95,6 +95,11 @@ impl UniformListScrollHandle {
     pub fn scroll_to_item(&mut self, ix: usize) {
         self.deferred_scroll_to_item.replace(Some(ix));
     }
+
+    /// Get the current scroll offset of the list.
+    pub fn offset(&self) -> Point<Pixels> {
+        self.base_handle.offset()
+    }
 }
 
 impl Styled for UniformList {
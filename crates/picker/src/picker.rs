@@ -13,13 +13,15 @@ use workspace::ModalView;
 
 mod head;
 pub mod highlighted_match_with_paths;
+#[cfg(test)]
+mod picker_tests;
 
 enum ElementContainer {
     List(ListState),
     UniformList(UniformListScrollHandle),
 }
 
-actions!(picker, [UseSelectedQuery]);
+actions!(picker, [UseSelectedQuery, SecondaryAction, ToggleSelection]);
 
 /// ConfirmInput is an alternative editor action which - instead of selecting active picker entry - treats pickers editor input literally,
 /// performing some kind of action on it.
@@ -40,6 +42,10 @@ pub struct Picker<D: PickerDelegate> {
     element_container: ElementContainer,
     head: Head,
     pending_update_matches: Option<PendingUpdateMatches>,
+    pending_debounced_update: Option<Task<()>>,
+    update_matches_debounce: Option<Duration>,
+    /// The query the delegate's matches were last updated for.
+    last_query: String,
     confirm_on_update: Option<bool>,
     width: Option<Length>,
     max_height: Option<Length>,
@@ -59,6 +65,17 @@ pub trait PickerDelegate: Sized + 'static {
         Vec::new()
     }
     fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>);
+    /// Whether the entry at `ix` is toggled for a batch operation, or `None` when multi-select is not in use.
+    /// The picker renders a checkmark column for entries that return `Some`.
+    fn toggled(&self, _ix: usize) -> Option<bool> {
+        None
+    }
+    fn selected_indices(&self) -> Vec<usize> {
+        (0..self.match_count())
+            .filter(|ix| self.toggled(*ix) == Some(true))
+            .collect()
+    }
+    fn toggle_selection(&mut self, _ix: usize, _: &mut ViewContext<Picker<Self>>) {}
     // Allows binding some optional effect to when the selection changes.
     fn selected_index_changed(
         &self,
@@ -94,6 +111,9 @@ pub trait PickerDelegate: Sized + 'static {
     /// Instead of interacting with currently selected entry, treats editor input literally,
     /// performing some kind of action on it.
     fn confirm_input(&mut self, _secondary: bool, _: &mut ViewContext<Picker<Self>>) {}
+    /// Performs a row-level secondary action (e.g. closing the item) on the entry at `ix`,
+    /// without confirming the picker.
+    fn secondary_action(&mut self, _ix: usize, _: &mut ViewContext<Picker<Self>>) {}
     fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>);
     fn should_dismiss(&self) -> bool {
         true
@@ -172,6 +192,9 @@ impl<D: PickerDelegate> Picker<D> {
             head,
             element_container: Self::create_element_container(container, cx),
             pending_update_matches: None,
+            pending_debounced_update: None,
+            update_matches_debounce: None,
+            last_query: String::new(),
             confirm_on_update: None,
             width: None,
             max_height: None,
@@ -222,6 +245,14 @@ impl<D: PickerDelegate> Picker<D> {
         self
     }
 
+    /// Delays querying the delegate until the input has not changed for `debounce`,
+    /// for delegates whose `update_matches` is expensive. Only typed input is debounced,
+    /// queries set via [`Picker::set_query`] are applied right away.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.update_matches_debounce = Some(debounce);
+        self
+    }
+
     pub fn modal(mut self, modal: bool) -> Self {
         self.is_modal = modal;
         self
@@ -241,16 +272,17 @@ impl<D: PickerDelegate> Picker<D> {
         let current_index = self.delegate.selected_index();
 
         if previous_index != current_index {
-            if let Some(action) = self.delegate.selected_index_changed(ix, cx) {
+            if let Some(action) = self.delegate.selected_index_changed(current_index, cx) {
                 action(cx);
             }
             if scroll_to_index {
-                self.scroll_to_item_index(ix);
+                self.scroll_to_item_index(current_index);
             }
         }
     }
 
     pub fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         let count = self.delegate.match_count();
         if count > 0 {
             let index = self.delegate.selected_index();
@@ -261,6 +293,7 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         let count = self.delegate.match_count();
         if count > 0 {
             let index = self.delegate.selected_index();
@@ -271,6 +304,7 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     fn select_first(&mut self, _: &menu::SelectFirst, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         let count = self.delegate.match_count();
         if count > 0 {
             self.set_selected_index(0, true, cx);
@@ -279,19 +313,20 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     fn select_last(&mut self, _: &menu::SelectLast, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         let count = self.delegate.match_count();
         if count > 0 {
-            self.delegate.set_selected_index(count - 1, cx);
             self.set_selected_index(count - 1, true, cx);
             cx.notify();
         }
     }
 
     pub fn cycle_selection(&mut self, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         let count = self.delegate.match_count();
         let index = self.delegate.selected_index();
         let new_index = if index + 1 == count { 0 } else { index + 1 };
-        self.set_selected_index(new_index, false, cx);
+        self.set_selected_index(new_index, true, cx);
         cx.notify();
     }
 
@@ -303,6 +338,7 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         if self.pending_update_matches.is_some()
             && !self
                 .delegate
@@ -316,6 +352,7 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     fn secondary_confirm(&mut self, _: &menu::SecondaryConfirm, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
         if self.pending_update_matches.is_some()
             && !self
                 .delegate
@@ -331,6 +368,35 @@ impl<D: PickerDelegate> Picker<D> {
         self.delegate.confirm_input(input.secondary, cx);
     }
 
+    fn secondary_action(&mut self, _: &SecondaryAction, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
+        if self.delegate.match_count() > 0 {
+            let ix = self.delegate.selected_index();
+            self.delegate.secondary_action(ix, cx);
+            cx.notify();
+        }
+    }
+
+    /// Toggles the entry at `ix` in or out of the multi-selection.
+    /// `ix` refers to the entries currently shown, even if a debounced query is pending.
+    pub fn toggle_selection(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix < self.delegate.match_count() {
+            self.delegate.toggle_selection(ix, cx);
+            cx.notify();
+        }
+    }
+
+    /// Returns the indices of the entries currently toggled via [`ToggleSelection`].
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.delegate.selected_indices()
+    }
+
+    fn on_toggle_selection(&mut self, _: &ToggleSelection, cx: &mut ViewContext<Self>) {
+        self.flush_debounced_update(cx);
+        let ix = self.delegate.selected_index();
+        self.toggle_selection(ix, cx);
+    }
+
     fn use_selected_query(&mut self, _: &UseSelectedQuery, cx: &mut ViewContext<Self>) {
         if let Some(new_query) = self.delegate.selected_as_query() {
             self.set_query(new_query, cx);
@@ -366,7 +432,12 @@ impl<D: PickerDelegate> Picker<D> {
         match event {
             editor::EditorEvent::BufferEdited => {
                 let query = editor.read(cx).text(cx);
-                self.update_matches(query, cx);
+                if query == self.last_query {
+                    // The matches are up to date already, e.g. after `set_query`.
+                    self.pending_debounced_update = None;
+                } else {
+                    self.schedule_update_matches(query, cx);
+                }
             }
             editor::EditorEvent::Blurred => {
                 self.cancel(&menu::Cancel, cx);
@@ -387,7 +458,31 @@ impl<D: PickerDelegate> Picker<D> {
         self.update_matches(query, cx);
     }
 
+    fn schedule_update_matches(&mut self, query: String, cx: &mut ViewContext<Self>) {
+        let Some(debounce) = self.update_matches_debounce else {
+            self.update_matches(query, cx);
+            return;
+        };
+        // Replacing the task drops the previous one, so superseded queries never reach the delegate.
+        self.pending_debounced_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(debounce).await;
+            this.update(&mut cx, |this, cx| this.update_matches(query, cx))
+                .ok();
+        }));
+    }
+
+    /// Runs a debounced query right away, so that actions on the selected entry
+    /// apply to the matches of the latest input.
+    fn flush_debounced_update(&mut self, cx: &mut ViewContext<Self>) {
+        if self.pending_debounced_update.is_some() {
+            let query = self.query(cx);
+            self.update_matches(query, cx);
+        }
+    }
+
     pub fn update_matches(&mut self, query: String, cx: &mut ViewContext<Self>) {
+        self.pending_debounced_update = None;
+        self.last_query = query.clone();
         let delegate_pending_update_matches = self.delegate.update_matches(query, cx);
 
         self.matches_updated(cx);
@@ -437,15 +532,18 @@ impl<D: PickerDelegate> Picker<D> {
         }
     }
 
-    pub fn set_query(&self, query: impl Into<Arc<str>>, cx: &mut ViewContext<Self>) {
+    pub fn set_query(&mut self, query: impl Into<Arc<str>>, cx: &mut ViewContext<Self>) {
         if let Head::Editor(ref editor) = &self.head {
+            let query = query.into();
             editor.update(cx, |editor, cx| {
-                editor.set_text(query, cx);
+                editor.set_text(query.clone(), cx);
                 let editor_offset = editor.buffer().read(cx).len(cx);
                 editor.change_selections(Some(Autoscroll::Next), cx, |s| {
                     s.select_ranges(Some(editor_offset..editor_offset))
                 });
             });
+            // Queries set programmatically are never debounced.
+            self.update_matches(query.to_string(), cx);
         }
     }
 
@@ -457,8 +555,15 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     fn render_element(&self, cx: &mut ViewContext<Self>, ix: usize) -> impl IntoElement {
+        let toggled = self.delegate.toggled(ix);
+        let match_element =
+            self.delegate
+                .render_match(ix, ix == self.delegate.selected_index(), cx);
+
         div()
             .id(("item", ix))
+            .debug_selector(|| format!("PICKER_ITEM-{ix}"))
+            .relative()
             .cursor_pointer()
             .on_click(cx.listener(move |this, event: &ClickEvent, cx| {
                 this.handle_click(ix, event.down.modifiers.secondary(), cx)
@@ -475,17 +580,35 @@ impl<D: PickerDelegate> Picker<D> {
                     this.handle_click(ix, event.modifiers.platform, cx)
                 }),
             )
-            .children(
-                self.delegate
-                    .render_match(ix, ix == self.delegate.selected_index(), cx),
-            )
+            .map(|el| match toggled {
+                None => el.children(match_element),
+                Some(toggled) => el.child(
+                    h_flex()
+                        .child(div().flex_none().pl_2().child(if toggled {
+                            Icon::new(IconName::Check)
+                                .color(Color::Accent)
+                                .into_any_element()
+                        } else {
+                            div().size(IconSize::default().rems()).into_any_element()
+                        }))
+                        .child(div().flex_1().children(match_element)),
+                ),
+            })
             .when(
                 self.delegate.separators_after_indices().contains(&ix),
                 |picker| {
-                    picker
-                        .border_color(cx.theme().colors().border_variant)
-                        .border_b_1()
-                        .pb(px(-1.0))
+                    // Overlaid on the row's bottom edge, so that uniform lists keep
+                    // the same height for every row.
+                    picker.child(
+                        div()
+                            .debug_selector(|| format!("PICKER_SEPARATOR-{ix}"))
+                            .absolute()
+                            .bottom_0()
+                            .left_0()
+                            .w_full()
+                            .h_px()
+                            .bg(cx.theme().colors().border_variant),
+                    )
                 },
             )
     }
@@ -537,6 +660,8 @@ impl<D: PickerDelegate> Render for Picker<D> {
             .on_action(cx.listener(Self::secondary_confirm))
             .on_action(cx.listener(Self::use_selected_query))
             .on_action(cx.listener(Self::confirm_input))
+            .on_action(cx.listener(Self::secondary_action))
+            .on_action(cx.listener(Self::on_toggle_selection))
             .child(match &self.head {
                 Head::Editor(editor) => v_flex()
                     .child(
@@ -561,16 +686,19 @@ impl<D: PickerDelegate> Render for Picker<D> {
                 )
             })
             .when(self.delegate.match_count() == 0, |el| {
+                let no_matches_text = self.delegate.no_matches_text(cx);
                 el.child(
-                    v_flex().flex_grow().py_2().child(
-                        ListItem::new("empty_state")
-                            .inset(true)
-                            .spacing(ListItemSpacing::Sparse)
-                            .disabled(true)
-                            .child(
-                                Label::new(self.delegate.no_matches_text(cx)).color(Color::Muted),
-                            ),
-                    ),
+                    v_flex()
+                        .debug_selector(|| format!("PICKER_EMPTY_STATE-{no_matches_text}"))
+                        .flex_grow()
+                        .py_2()
+                        .child(
+                            ListItem::new("empty_state")
+                                .inset(true)
+                                .spacing(ListItemSpacing::Sparse)
+                                .disabled(true)
+                                .child(Label::new(no_matches_text).color(Color::Muted)),
+                        ),
                 )
             })
             .children(self.delegate.render_footer(cx))
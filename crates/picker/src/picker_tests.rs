@@ -0,0 +1,343 @@
+use super::*;
+use gpui::{Modifiers, TestAppContext, VisualTestContext};
+use project::Project;
+use workspace::AppState;
+
+#[ctor::ctor]
+fn init_logger() {
+    if std::env::var("RUST_LOG").is_ok() {
+        env_logger::init();
+    }
+}
+
+#[gpui::test]
+async fn test_secondary_action_is_dispatched_for_selected_index(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_picker(TestDelegate::new(5), cx);
+
+    cx.dispatch_action(menu::SelectNext);
+    cx.dispatch_action(menu::SelectNext);
+    cx.dispatch_action(SecondaryAction);
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.selected_index(), 2);
+        assert_eq!(picker.delegate.secondary_actions, vec![2]);
+    });
+
+    cx.dispatch_action(menu::SelectFirst);
+    cx.dispatch_action(SecondaryAction);
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.secondary_actions, vec![2, 0]);
+    });
+}
+
+#[gpui::test]
+async fn test_selection_is_scrolled_into_view(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_picker(TestDelegate::new(100), cx);
+    assert_eq!(scroll_offset(&picker, cx), px(0.));
+
+    cx.dispatch_action(menu::SelectLast);
+    let last_candidate_offset = scroll_offset(&picker, cx);
+    assert!(
+        last_candidate_offset < px(0.),
+        "list should scroll down to the last candidate"
+    );
+
+    // The previous candidate is already visible, so the list stays put.
+    cx.dispatch_action(menu::SelectPrev);
+    assert_eq!(scroll_offset(&picker, cx), last_candidate_offset);
+
+    // Cycling past the last candidate wraps around to the top of the list.
+    picker.update(cx, |picker, cx| picker.cycle_selection(cx));
+    picker.update(cx, |picker, cx| picker.cycle_selection(cx));
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.selected_index(), 0)
+    });
+    assert_eq!(scroll_offset(&picker, cx), px(0.));
+}
+
+#[gpui::test]
+async fn test_typed_queries_are_debounced(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_debounced_picker(TestDelegate::new(5), cx);
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec![""])
+    });
+
+    cx.simulate_input("candidate");
+    cx.executor().advance_clock(DEBOUNCE / 2);
+    cx.simulate_input(" 3");
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec![""])
+    });
+
+    cx.executor().advance_clock(DEBOUNCE);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "candidate 3"]);
+        assert_eq!(picker.delegate.matches, vec![3]);
+    });
+}
+
+#[gpui::test]
+async fn test_set_query_is_not_debounced(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_debounced_picker(TestDelegate::new(5), cx);
+
+    picker.update(cx, |picker, cx| picker.set_query("2", cx));
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "2"]);
+        assert_eq!(picker.delegate.matches, vec![2]);
+    });
+    cx.executor().advance_clock(DEBOUNCE);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "2"])
+    });
+
+    // Setting a query cancels a pending typed one.
+    cx.simulate_input("x");
+    picker.update(cx, |picker, cx| picker.set_query("4", cx));
+    cx.executor().advance_clock(DEBOUNCE);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "2", "4"]);
+        assert_eq!(picker.delegate.matches, vec![4]);
+    });
+
+    // Typing afterwards is debounced again.
+    cx.simulate_input("x");
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "2"])
+    });
+}
+
+#[gpui::test]
+async fn test_index_actions_flush_debounced_query(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_debounced_picker(TestDelegate::new(5), cx);
+
+    cx.simulate_input("3");
+    cx.dispatch_action(SecondaryAction);
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "3"]);
+        assert_eq!(picker.delegate.matches, vec![3]);
+        assert_eq!(picker.delegate.secondary_actions, vec![0]);
+    });
+
+    // The flushed query is not run a second time once the debounce elapses.
+    cx.executor().advance_clock(DEBOUNCE);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "3"])
+    });
+}
+
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+#[gpui::test]
+async fn test_click_and_toggle_act_on_shown_rows_while_query_is_pending(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_debounced_picker(TestDelegate::new(5), cx);
+
+    cx.simulate_input("3");
+    picker.update(cx, |picker, cx| picker.toggle_selection(1, cx));
+    let third_row = cx
+        .debug_bounds("PICKER_ITEM-2")
+        .expect("third row is not rendered");
+    cx.simulate_click(third_row.center(), Modifiers::none());
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec![""]);
+        assert_eq!(picker.delegate.toggled, vec![1]);
+        assert_eq!(picker.delegate.confirmed, vec![2]);
+    });
+
+    cx.executor().advance_clock(DEBOUNCE);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(picker.delegate.queries, vec!["", "3"]);
+        assert_eq!(picker.delegate.matches, vec![3]);
+    });
+}
+
+#[gpui::test]
+async fn test_no_matches_text_is_rendered(cx: &mut TestAppContext) {
+    init_test(cx);
+    let (picker, cx) = open_picker(TestDelegate::new(5), cx);
+    assert!(cx
+        .debug_bounds("PICKER_EMPTY_STATE-No candidates match 'zzz'")
+        .is_none());
+
+    cx.simulate_input("zzz");
+    picker.update(cx, |picker, _| assert_eq!(picker.delegate.match_count(), 0));
+    assert!(cx
+        .debug_bounds("PICKER_EMPTY_STATE-No candidates match 'zzz'")
+        .is_some());
+}
+
+#[gpui::test]
+async fn test_separators_are_rendered_after_indices(cx: &mut TestAppContext) {
+    init_test(cx);
+    let delegate = TestDelegate {
+        separators: vec![2],
+        ..TestDelegate::new(5)
+    };
+    let (_picker, cx) = open_picker(delegate, cx);
+
+    let third_row = cx
+        .debug_bounds("PICKER_ITEM-2")
+        .expect("third row is not rendered");
+    let separator = cx
+        .debug_bounds("PICKER_SEPARATOR-2")
+        .expect("no separator after the third row");
+    assert_eq!(separator.bottom(), third_row.bottom());
+    assert!(separator.size.height < third_row.size.height);
+
+    for other_separator in [
+        "PICKER_SEPARATOR-0",
+        "PICKER_SEPARATOR-1",
+        "PICKER_SEPARATOR-3",
+        "PICKER_SEPARATOR-4",
+    ] {
+        assert!(cx.debug_bounds(other_separator).is_none());
+    }
+}
+
+struct TestDelegate {
+    candidates: Vec<String>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    secondary_actions: Vec<usize>,
+    confirmed: Vec<usize>,
+    toggled: Vec<usize>,
+    queries: Vec<String>,
+    separators: Vec<usize>,
+}
+
+impl TestDelegate {
+    fn new(candidate_count: usize) -> Self {
+        Self {
+            candidates: (0..candidate_count)
+                .map(|ix| format!("candidate {ix}"))
+                .collect(),
+            matches: Vec::new(),
+            selected_index: 0,
+            secondary_actions: Vec::new(),
+            confirmed: Vec::new(),
+            toggled: Vec::new(),
+            separators: Vec::new(),
+            queries: Vec::new(),
+        }
+    }
+}
+
+impl PickerDelegate for TestDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn separators_after_indices(&self) -> Vec<usize> {
+        self.separators.clone()
+    }
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        "".into()
+    }
+
+    fn no_matches_text(&self, _: &mut WindowContext) -> SharedString {
+        let query = self.queries.last().map_or("", String::as_str);
+        format!("No candidates match '{query}'").into()
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        self.queries.push(query.clone());
+        self.matches = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| candidate.contains(query.as_str()))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn toggle_selection(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.toggled.push(self.matches[ix]);
+    }
+
+    fn confirm(&mut self, _: bool, _: &mut ViewContext<Picker<Self>>) {
+        self.confirmed.push(self.matches[self.selected_index]);
+    }
+
+    fn secondary_action(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.secondary_actions.push(ix);
+    }
+
+    fn dismissed(&mut self, _: &mut ViewContext<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(Label::new(self.candidates[self.matches[ix]].clone())),
+        )
+    }
+}
+
+fn init_test(cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        AppState::test(cx);
+        theme::init(theme::LoadThemes::JustBase, cx);
+        language::init(cx);
+        editor::init(cx);
+        workspace::init_settings(cx);
+        Project::init_settings(cx);
+    });
+}
+
+fn open_picker(
+    delegate: TestDelegate,
+    cx: &mut TestAppContext,
+) -> (View<Picker<TestDelegate>>, &mut VisualTestContext) {
+    let (picker, cx) = cx.add_window_view(|cx| Picker::uniform_list(delegate, cx));
+    picker.update(cx, |picker, cx| picker.focus(cx));
+    (picker, cx)
+}
+
+fn open_debounced_picker(
+    delegate: TestDelegate,
+    cx: &mut TestAppContext,
+) -> (View<Picker<TestDelegate>>, &mut VisualTestContext) {
+    let (picker, cx) =
+        cx.add_window_view(|cx| Picker::uniform_list(delegate, cx).debounce(DEBOUNCE));
+    picker.update(cx, |picker, cx| picker.focus(cx));
+    (picker, cx)
+}
+
+fn scroll_offset(picker: &View<Picker<TestDelegate>>, cx: &mut VisualTestContext) -> Pixels {
+    picker.update(cx, |picker, _| match &picker.element_container {
+        ElementContainer::UniformList(scroll_handle) => scroll_handle.offset().y,
+        ElementContainer::List(_) => unreachable!("test pickers use a uniform list"),
+    })
+}
@@ -7,7 +7,7 @@ use gpui::{
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
 use project::{Project, Symbol};
-use std::{borrow::Cow, cmp::Reverse, sync::Arc};
+use std::{borrow::Cow, cmp::Reverse, sync::Arc, time::Duration};
 use theme::ActiveTheme;
 use util::ResultExt;
 use workspace::{
@@ -17,6 +17,10 @@ use workspace::{
 
 actions!(project_symbols, [Toggle]);
 
+/// Every query sends a workspace symbols request to the language servers,
+/// so there is no point in sending one per keystroke.
+const UPDATE_MATCHES_DEBOUNCE: Duration = Duration::from_millis(100);
+
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
@@ -25,7 +29,9 @@ pub fn init(cx: &mut AppContext) {
                 let handle = cx.view().downgrade();
                 workspace.toggle_modal(cx, move |cx| {
                     let delegate = ProjectSymbolsDelegate::new(handle, project);
-                    Picker::uniform_list(delegate, cx).width(rems(34.))
+                    Picker::uniform_list(delegate, cx)
+                        .width(rems(34.))
+                        .debounce(UPDATE_MATCHES_DEBOUNCE)
                 })
             });
         },
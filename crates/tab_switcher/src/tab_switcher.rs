@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tab_switcher_tests;
 
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use gpui::{
     actions, impl_actions, rems, Action, AnyElement, AppContext, DismissEvent, EntityId,
     EventEmitter, FocusHandle, FocusableView, Modifiers, ModifiersChangedEvent, MouseButton,
@@ -10,12 +10,12 @@ use gpui::{
 use picker::{Picker, PickerDelegate};
 use serde::Deserialize;
 use std::sync::Arc;
-use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use ui::{prelude::*, KeyBinding, ListItem, ListItemSpacing, Tooltip};
 use util::ResultExt;
 use workspace::{
     item::{ItemHandle, TabContentParams},
-    pane::{render_item_indicator, tab_details, Event as PaneEvent},
-    ModalView, Pane, SaveIntent, Workspace,
+    pane::{render_item_indicator, tab_details, ActivationHistoryEntry, Event as PaneEvent},
+    DismissDecision, ModalView, Pane, SaveIntent, Workspace,
 };
 
 const PANEL_WIDTH_REMS: f32 = 28.;
@@ -34,7 +34,15 @@ pub struct TabSwitcher {
     init_modifiers: Option<Modifiers>,
 }
 
-impl ModalView for TabSwitcher {}
+impl ModalView for TabSwitcher {
+    fn on_before_dismiss(&mut self, cx: &mut ViewContext<Self>) -> DismissDecision {
+        // The modal layer may close the switcher without dismissing the picker,
+        // e.g. when another modal is opened.
+        self.picker
+            .update(cx, |picker, cx| picker.delegate.restore_pane(false, cx));
+        DismissDecision::Dismiss(true)
+    }
+}
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(TabSwitcher::register).detach();
@@ -102,7 +110,7 @@ impl TabSwitcher {
         self.picker.update(cx, |picker, cx| {
             picker
                 .delegate
-                .close_item_at(picker.delegate.selected_index(), cx)
+                .close_selected_items(picker.delegate.selected_index(), cx)
         });
     }
 }
@@ -133,12 +141,21 @@ struct TabMatch {
     preview: bool,
 }
 
+/// The pane state from before the switcher started previewing the highlighted tabs.
+struct PaneSnapshot {
+    active_item: Option<Box<dyn ItemHandle>>,
+    activation_history: Vec<ActivationHistoryEntry>,
+}
+
 pub struct TabSwitcherDelegate {
     select_last: bool,
     tab_switcher: WeakView<TabSwitcher>,
     selected_index: usize,
     pane: WeakView<Pane>,
     matches: Vec<TabMatch>,
+    recent_match_count: usize,
+    multi_selection: HashSet<EntityId>,
+    pane_snapshot: Option<PaneSnapshot>,
 }
 
 impl TabSwitcherDelegate {
@@ -149,12 +166,22 @@ impl TabSwitcherDelegate {
         cx: &mut ViewContext<TabSwitcher>,
     ) -> Self {
         Self::subscribe_to_updates(&pane, cx);
+        let pane_snapshot = pane.upgrade().map(|pane| {
+            let pane = pane.read(cx);
+            PaneSnapshot {
+                active_item: pane.active_item(),
+                activation_history: pane.activation_history().to_vec(),
+            }
+        });
         Self {
             select_last: action.select_last,
             tab_switcher,
             selected_index: 0,
             pane,
             matches: Vec::new(),
+            recent_match_count: 0,
+            multi_selection: HashSet::default(),
+            pane_snapshot,
         }
     }
 
@@ -187,12 +214,21 @@ impl TabSwitcherDelegate {
         };
 
         let pane = pane.read(cx);
+        // Previews reorder the pane's history, so stick to the order the switcher was opened with.
+        let activation_history = self
+            .pane_snapshot
+            .as_ref()
+            .map_or(pane.activation_history(), |snapshot| {
+                snapshot.activation_history.as_slice()
+            });
         let mut history_indices = HashMap::default();
-        pane.activation_history().iter().rev().enumerate().for_each(
-            |(history_index, history_entry)| {
+        activation_history
+            .iter()
+            .rev()
+            .enumerate()
+            .for_each(|(history_index, history_entry)| {
                 history_indices.insert(history_entry.entity_id, history_index);
-            },
-        );
+            });
 
         let items: Vec<Box<dyn ItemHandle>> = pane.items().map(|item| item.boxed_clone()).collect();
         items
@@ -207,6 +243,11 @@ impl TabSwitcherDelegate {
             })
             .for_each(|tab_match| self.matches.push(tab_match));
 
+        self.recent_match_count = self
+            .matches
+            .iter()
+            .filter(|tab_match| history_indices.contains_key(&tab_match.item.item_id()))
+            .count();
         let non_history_base = history_indices.len();
         self.matches.sort_by(move |a, b| {
             let a_score = *history_indices
@@ -218,6 +259,14 @@ impl TabSwitcherDelegate {
             a_score.cmp(&b_score)
         });
 
+        // Tabs leave the selection only once they are actually closed, as closing may be cancelled.
+        let matches = &self.matches;
+        self.multi_selection.retain(|item_id| {
+            matches
+                .iter()
+                .any(|tab_match| tab_match.item.item_id() == *item_id)
+        });
+
         if self.matches.len() > 1 {
             if self.select_last {
                 self.selected_index = self.matches.len() - 1;
@@ -246,16 +295,64 @@ impl TabSwitcherDelegate {
         self.set_selected_index(selected_idx, cx);
     }
 
-    fn close_item_at(&mut self, ix: usize, cx: &mut ViewContext<'_, Picker<TabSwitcherDelegate>>) {
-        let Some(tab_match) = self.matches.get(ix) else {
+    /// Closes all toggled tabs if there are any, and the tab at `ix` otherwise.
+    fn close_selected_items(
+        &mut self,
+        ix: usize,
+        cx: &mut ViewContext<'_, Picker<TabSwitcherDelegate>>,
+    ) {
+        let selected_indices = self.selected_indices();
+        if selected_indices.is_empty() {
+            self.close_item_at(ix, cx);
+        } else {
+            self.close_items_at(&selected_indices, cx);
+        }
+    }
+
+    /// Puts the pane back the way it was before previewing, re-activating the original tab
+    /// unless a tab has been confirmed.
+    fn restore_pane(&mut self, confirmed: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(snapshot) = self.pane_snapshot.take() else {
+            return;
+        };
+        let Some(pane) = self.pane.upgrade() else {
             return;
         };
+        pane.update(cx, |pane, cx| {
+            if !confirmed {
+                let original_index = snapshot
+                    .active_item
+                    .and_then(|item| pane.index_for_item(item.as_ref()));
+                if let Some(index) = original_index {
+                    pane.activate_item(index, false, false, cx);
+                }
+            }
+            pane.set_activation_history(snapshot.activation_history);
+        });
+    }
+
+    fn close_item_at(&mut self, ix: usize, cx: &mut ViewContext<'_, Picker<TabSwitcherDelegate>>) {
+        self.close_items_at(&[ix], cx);
+    }
+
+    fn close_items_at(
+        &mut self,
+        indices: &[usize],
+        cx: &mut ViewContext<'_, Picker<TabSwitcherDelegate>>,
+    ) {
         let Some(pane) = self.pane.upgrade() else {
             return;
         };
+        let item_ids = indices
+            .iter()
+            .filter_map(|ix| self.matches.get(*ix))
+            .map(|tab_match| tab_match.item.item_id())
+            .collect::<Vec<_>>();
         pane.update(cx, |pane, cx| {
-            pane.close_item_by_id(tab_match.item.item_id(), SaveIntent::Close, cx)
-                .detach_and_log_err(cx);
+            pane.close_items(cx, SaveIntent::Close, move |item_id| {
+                item_ids.contains(&item_id)
+            })
+            .detach_and_log_err(cx);
         });
     }
 }
@@ -285,7 +382,48 @@ impl PickerDelegate for TabSwitcherDelegate {
     }
 
     fn separators_after_indices(&self) -> Vec<usize> {
-        Vec::new()
+        // Recently activated tabs come first, separated from the tabs that were never activated.
+        if self.recent_match_count > 0 && self.recent_match_count < self.matches.len() {
+            vec![self.recent_match_count - 1]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn toggled(&self, ix: usize) -> Option<bool> {
+        if self.multi_selection.is_empty() {
+            return None;
+        }
+        let tab_match = self.matches.get(ix)?;
+        Some(self.multi_selection.contains(&tab_match.item.item_id()))
+    }
+
+    fn toggle_selection(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(tab_match) = self.matches.get(ix) else {
+            return;
+        };
+        let item_id = tab_match.item.item_id();
+        if !self.multi_selection.remove(&item_id) {
+            self.multi_selection.insert(item_id);
+        }
+        cx.notify();
+    }
+
+    fn selected_index_changed(
+        &self,
+        ix: usize,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Box<dyn Fn(&mut WindowContext) + 'static>> {
+        let item = self.matches.get(ix)?.item.boxed_clone();
+        let pane = self.pane.clone();
+        Some(Box::new(move |cx| {
+            pane.update(cx, |pane, cx| {
+                if let Some(index) = pane.index_for_item(item.as_ref()) {
+                    pane.activate_item(index, false, false, cx);
+                }
+            })
+            .log_err();
+        }))
     }
 
     fn update_matches(
@@ -304,17 +442,62 @@ impl PickerDelegate for TabSwitcherDelegate {
         let Some(selected_match) = self.matches.get(self.selected_index()) else {
             return;
         };
+        let item_index = selected_match.item_index;
+        self.restore_pane(true, cx);
         pane.update(cx, |pane, cx| {
-            pane.activate_item(selected_match.item_index, true, true, cx);
+            pane.activate_item(item_index, true, true, cx);
         });
     }
 
+    fn secondary_action(&mut self, ix: usize, cx: &mut ViewContext<Picker<TabSwitcherDelegate>>) {
+        self.close_selected_items(ix, cx);
+    }
+
     fn dismissed(&mut self, cx: &mut ViewContext<Picker<TabSwitcherDelegate>>) {
+        self.restore_pane(false, cx);
         self.tab_switcher
             .update(cx, |_, cx| cx.emit(DismissEvent))
             .log_err();
     }
 
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        // The switcher is usually opened with a modifier held, which turns confirming into
+        // secondary confirming, so show the binding that is going to apply.
+        let open_action = if cx.modifiers().secondary() {
+            menu::SecondaryConfirm.boxed_clone()
+        } else {
+            menu::Confirm.boxed_clone()
+        };
+        Some(
+            h_flex()
+                .w_full()
+                .h_8()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .rounded_b_md()
+                .bg(cx.theme().colors().ghost_element_selected)
+                .children(
+                    KeyBinding::for_action(&CloseSelectedItem, cx).map(|keybind| {
+                        Button::new("close-selected", "Close")
+                            .label_size(LabelSize::Small)
+                            .key_binding(keybind)
+                            .on_click(|_, cx| cx.dispatch_action(CloseSelectedItem.boxed_clone()))
+                    }),
+                )
+                .children(KeyBinding::for_action(&*open_action, cx).map(|keybind| {
+                    Button::new("open-selected", "Open")
+                        .label_size(LabelSize::Small)
+                        .key_binding(keybind)
+                        .on_click(move |_, cx| cx.dispatch_action(open_action.boxed_clone()))
+                }))
+                .into_any_element(),
+        )
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -1,7 +1,7 @@
 use super::*;
 use editor::Editor;
 use gpui::{TestAppContext, VisualTestContext};
-use menu::SelectPrev;
+use menu::{SelectNext, SelectPrev};
 use project::{Project, ProjectPath};
 use serde_json::json;
 use std::path::Path;
@@ -103,6 +103,8 @@ async fn test_open_with_last_tab_selected(cx: &mut gpui::TestAppContext) {
         assert_match_at_position(tab_switcher, 0, tab_3);
         assert_match_at_position(tab_switcher, 1, tab_2);
         assert_match_selection(tab_switcher, 2, tab_1);
+        // Every tab has been activated, so there is nothing to separate.
+        assert!(tab_switcher.delegate.separators_after_indices().is_empty());
     });
 }
 
@@ -228,6 +230,357 @@ async fn test_close_selected_item(cx: &mut gpui::TestAppContext) {
     assert_tab_switcher_is_closed(workspace, cx);
 }
 
+#[gpui::test]
+async fn test_secondary_action_closes_selected_item(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 3);
+        assert_match_at_position(tab_switcher, 0, tab_3.boxed_clone());
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+        assert_match_at_position(tab_switcher, 2, tab_1.boxed_clone());
+    });
+
+    cx.dispatch_action(picker::SecondaryAction);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 2);
+        assert_match_at_position(tab_switcher, 0, tab_3);
+        assert_match_at_position(tab_switcher, 1, tab_1);
+    });
+}
+
+#[gpui::test]
+async fn test_toggle_selection(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        assert!(tab_switcher.selected_indices().is_empty());
+
+        tab_switcher.toggle_selection(0, cx);
+        tab_switcher.toggle_selection(2, cx);
+        assert_eq!(tab_switcher.selected_indices(), vec![0, 2]);
+    });
+
+    // The action toggles the highlighted row.
+    cx.dispatch_action(picker::ToggleSelection);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.selected_index(), 1);
+        assert_eq!(tab_switcher.selected_indices(), vec![0, 1, 2]);
+    });
+
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        tab_switcher.toggle_selection(0, cx);
+        assert_eq!(tab_switcher.selected_indices(), vec![1, 2]);
+    });
+}
+
+#[gpui::test]
+async fn test_close_multiple_selected_items(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    cx.simulate_modifiers_change(Modifiers::control());
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        assert_match_at_position(tab_switcher, 0, tab_3.boxed_clone());
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+        assert_match_at_position(tab_switcher, 2, tab_1.boxed_clone());
+
+        tab_switcher.toggle_selection(0, cx);
+        tab_switcher.toggle_selection(2, cx);
+    });
+
+    cx.dispatch_action(CloseSelectedItem);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 1);
+        assert_match_selection(tab_switcher, 0, tab_2);
+        assert!(tab_switcher.selected_indices().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_close_multiple_dirty_items_prompts_once(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+    for tab in [&tab_1, &tab_3] {
+        tab.downcast::<Editor>()
+            .unwrap()
+            .update(cx, |editor, cx| editor.insert("edit ", cx));
+    }
+
+    cx.simulate_modifiers_change(Modifiers::control());
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        tab_switcher.toggle_selection(0, cx);
+        tab_switcher.toggle_selection(2, cx);
+    });
+
+    cx.dispatch_action(CloseSelectedItem);
+    cx.run_until_parked();
+    assert!(cx.has_pending_prompt());
+
+    // A single "Discard all" answer closes both tabs without further prompts.
+    cx.simulate_prompt_answer(1);
+    cx.run_until_parked();
+    assert!(!cx.has_pending_prompt());
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 1);
+        assert_match_selection(tab_switcher, 0, tab_2);
+        assert!(tab_switcher.selected_indices().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_preview_selected_item_and_restore_on_dismiss(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    cx.simulate_modifiers_change(Modifiers::control());
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    assert_active_item(&workspace, &tab_3, cx);
+
+    cx.dispatch_action(SelectNext);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 2, tab_1.boxed_clone());
+    });
+    assert_active_item(&workspace, &tab_1, cx);
+
+    cx.dispatch_action(SelectPrev);
+    assert_active_item(&workspace, &tab_2, cx);
+
+    cx.dispatch_action(menu::Cancel);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    assert_active_item(&workspace, &tab_3, cx);
+
+    // Previewed tabs don't count as recently used.
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_at_position(tab_switcher, 0, tab_3.boxed_clone());
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+        assert_match_at_position(tab_switcher, 2, tab_1.boxed_clone());
+    });
+}
+
+#[gpui::test]
+async fn test_confirm_previewed_item(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    cx.simulate_modifiers_change(Modifiers::control());
+    open_tab_switcher(false, &workspace, cx);
+    cx.dispatch_action(SelectNext);
+    cx.dispatch_action(SelectPrev);
+    cx.dispatch_action(SelectNext);
+    assert_active_item(&workspace, &tab_1, cx);
+
+    cx.simulate_modifiers_change(Modifiers::none());
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    assert_active_item(&workspace, &tab_1, cx);
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_at_position(tab_switcher, 0, tab_1.boxed_clone());
+        assert_match_selection(tab_switcher, 1, tab_3.boxed_clone());
+        assert_match_at_position(tab_switcher, 2, tab_2.boxed_clone());
+    });
+}
+
+#[gpui::test]
+async fn test_restore_preview_when_other_modal_opens(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    cx.simulate_modifiers_change(Modifiers::control());
+    open_tab_switcher(false, &workspace, cx);
+    cx.dispatch_action(SelectNext);
+    assert_active_item(&workspace, &tab_1, cx);
+
+    workspace.update(cx, |workspace, cx| {
+        workspace.toggle_modal(cx, TestModal::new)
+    });
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    assert_active_item(&workspace, &tab_3, cx);
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_at_position(tab_switcher, 0, tab_3.boxed_clone());
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+        assert_match_at_position(tab_switcher, 2, tab_1.boxed_clone());
+    });
+}
+
+#[gpui::test]
+async fn test_footer_renders_key_hints(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+            }),
+        )
+        .await;
+    cx.update(|cx| {
+        cx.bind_keys([
+            gpui::KeyBinding::new("enter", menu::Confirm, None),
+            gpui::KeyBinding::new("ctrl-o", menu::SecondaryConfirm, None),
+            gpui::KeyBinding::new("ctrl-w", CloseSelectedItem, Some("TabSwitcher")),
+        ])
+    });
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+    open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    cx.run_until_parked();
+    assert!(cx.debug_bounds("KEY_BINDING-enter").is_some());
+    assert!(cx.debug_bounds("KEY_BINDING-o").is_none());
+    assert!(cx.debug_bounds("KEY_BINDING-w").is_some());
+
+    // Holding the secondary modifier shows the secondary confirm binding instead.
+    cx.simulate_modifiers_change(Modifiers::secondary_key());
+    tab_switcher.update(cx, |_, cx| cx.notify());
+    cx.run_until_parked();
+    assert!(cx.debug_bounds("KEY_BINDING-enter").is_none());
+    assert!(cx.debug_bounds("KEY_BINDING-o").is_some());
+    assert!(cx.debug_bounds("KEY_BINDING-w").is_some());
+}
+
 fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
     cx.update(|cx| {
         let state = AppState::test(cx);
@@ -325,3 +678,39 @@ fn assert_tab_switcher_is_closed(workspace: View<Workspace>, cx: &mut VisualTest
         );
     });
 }
+
+#[track_caller]
+fn assert_active_item(
+    workspace: &View<Workspace>,
+    expected_item: &dyn ItemHandle,
+    cx: &mut VisualTestContext,
+) {
+    workspace.update(cx, |workspace, cx| {
+        let active_item = workspace.active_item(cx).expect("no active item");
+        assert_eq!(active_item.item_id(), expected_item.item_id());
+    });
+}
+
+struct TestModal(FocusHandle);
+
+impl TestModal {
+    fn new(cx: &mut ViewContext<Self>) -> Self {
+        Self(cx.focus_handle())
+    }
+}
+
+impl ModalView for TestModal {}
+
+impl EventEmitter<DismissEvent> for TestModal {}
+
+impl FocusableView for TestModal {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.0.clone()
+    }
+}
+
+impl Render for TestModal {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        div().track_focus(&self.0)
+    }
+}
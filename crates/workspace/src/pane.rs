@@ -221,6 +221,7 @@ pub struct Pane {
     save_modals_spawned: HashSet<EntityId>,
 }
 
+#[derive(Clone)]
 pub struct ActivationHistoryEntry {
     pub entity_id: EntityId,
     pub timestamp: usize,
@@ -519,6 +520,18 @@ impl Pane {
         &self.activation_history
     }
 
+    /// Replaces the activation history with one taken earlier, skipping items closed since.
+    pub fn set_activation_history(&mut self, history: Vec<ActivationHistoryEntry>) {
+        self.activation_history = history
+            .into_iter()
+            .filter(|entry| {
+                self.items
+                    .iter()
+                    .any(|item| item.item_id() == entry.entity_id)
+            })
+            .collect();
+    }
+
     pub fn set_should_display_tab_bar<F>(&mut self, should_display_tab_bar: F)
     where
         F: 'static + Fn(&ViewContext<Pane>) -> bool,